@@ -129,6 +129,18 @@ export const generateNormalMapFromTexture = async (
   return normalMapTexture;
 };
 
+/**
+ * Controls how textures are blended within the crossfade region.  `smoothstep` and `quintic` soften the
+ * visible linear ramps at tile corners.
+ */
+export type CrossfadeBlendMode = 'linear' | 'smoothstep' | 'quintic';
+
+const CROSSFADE_BLEND_MODE_IDS: Record<CrossfadeBlendMode, number> = {
+  linear: 0,
+  smoothstep: 1,
+  quintic: 2,
+};
+
 export const genCrossfadedTexture = async (
   textures: ImageBitmap[],
   threshold: number,
@@ -141,7 +153,8 @@ export const genCrossfadedTexture = async (
     format = THREE.RGBAFormat,
     type = THREE.UnsignedByteType,
     anisotropy = 1,
-  }: TextureArgs = {}
+  }: TextureArgs = {},
+  blendMode: CrossfadeBlendMode = 'linear'
 ): Promise<THREE.Texture> => {
   const workersP = getTextureCrossfadeWorkers();
   const canvas = document.createElement('canvas');
@@ -157,7 +170,7 @@ export const genCrossfadedTexture = async (
 
   const workerPool = await workersP;
   const crossfadedTextureBytes: Uint8Array = await workerPool.submitWork(worker =>
-    worker.genCrossfadedTexture(textureData, canvas.width, threshold)
+    worker.genCrossfadedTexture(textureData, canvas.width, threshold, CROSSFADE_BLEND_MODE_IDS[blendMode])
   );
   if (
     crossfadedTextureBytes.length !==
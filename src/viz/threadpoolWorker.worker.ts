@@ -42,7 +42,12 @@ const methods = {
 
     return Comlink.transfer(generatedMapData, [generatedMapData.buffer]);
   },
-  genCrossfadedTexture: async (textures: Uint8Array[], textureSize: number, threshold: number) => {
+  genCrossfadedTexture: async (
+    textures: Uint8Array[],
+    textureSize: number,
+    threshold: number,
+    blendMode = 0
+  ) => {
     const engine = await textureCrossfadeEngineP;
     if (!engine) {
       throw new Error('Wasm not loaded');
@@ -56,7 +61,7 @@ const methods = {
       (engine.exports.set_texture as Function)(bufPtr, i);
     });
 
-    const outPtr: number = (engine.exports.generate as Function)(textureSize, threshold);
+    const outPtr: number = (engine.exports.generate as Function)(textureSize, threshold, blendMode);
     let memory = new Uint8Array((engine.exports.memory as WebAssembly.Memory).buffer);
     const out = new Uint8Array(
       memory.buffer.slice(outPtr, outPtr + textures.length * textureSize * textureSize * textures.length * 4)
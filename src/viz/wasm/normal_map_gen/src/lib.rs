@@ -8,21 +8,54 @@ fn normalize(a: f32, b: f32, c: f32) -> [f32; 3] {
   [a / len, b / len, c / len]
 }
 
+#[derive(Clone, Copy)]
+enum TilingMode {
+  /// Samples past the edge of the texture are clamped to the border pixels.
+  Clamp,
+  /// Samples past the edge of the texture wrap around to the opposite side so
+  /// that normal maps generated from tiling textures are seamless.
+  Wrap,
+}
+
+impl TilingMode {
+  fn from_u8(tiling_mode: u8) -> Self {
+    match tiling_mode {
+      0 => TilingMode::Clamp,
+      1 => TilingMode::Wrap,
+      _ => panic!("Invalid tiling mode"),
+    }
+  }
+}
+
+/// Returns the indices of the two pixels to interpolate between along a single
+/// axis along with the interpolation ratio between them.
+fn bilinear_axis_coords(v: f32, size: usize, tiling_mode: TilingMode) -> (usize, usize, f32) {
+  match tiling_mode {
+    TilingMode::Clamp => {
+      let v = v.max(0.0).min(size as f32 - 2.0);
+      let v0 = v.floor() as usize;
+      (v0, v0 + 1, v - v0 as f32)
+    }
+    TilingMode::Wrap => {
+      let v = v.rem_euclid(size as f32);
+      let v_floor = v.floor();
+      // `rem_euclid` can round up to exactly `size` for tiny negative inputs
+      let v0 = (v_floor as usize) % size;
+      (v0, (v0 + 1) % size, v - v_floor)
+    }
+  }
+}
+
 fn read_interpolated_bilinear_f32(
   texture: &[f32],
   width: usize,
   height: usize,
   x: f32,
   y: f32,
+  tiling_mode: TilingMode,
 ) -> [f32; 3] {
-  let x = x.max(0.0).min(width as f32 - 2.0);
-  let y = y.max(0.0).min(height as f32 - 2.0);
-  let x0 = x.floor() as usize;
-  let y0 = y.floor() as usize;
-  let x1 = x0 + 1;
-  let y1 = y0 + 1;
-  let x_ratio = x - x0 as f32;
-  let y_ratio = y - y0 as f32;
+  let (x0, x1, x_ratio) = bilinear_axis_coords(x, width, tiling_mode);
+  let (y0, y1, y_ratio) = bilinear_axis_coords(y, height, tiling_mode);
 
   let (x0y0, x1y0, x0y1, x1y1) = if cfg!(debug_assertions) {
     let x0y0 = [
@@ -96,15 +129,10 @@ fn read_interpolated_bilinear_f32_simd(
   height: usize,
   x: f32,
   y: f32,
+  tiling_mode: TilingMode,
 ) -> [f32; 3] {
-  let x = x.min(width as f32 - 2.0);
-  let y = y.min(height as f32 - 2.0);
-  let x0 = x.floor() as usize;
-  let y0 = y.floor() as usize;
-  let x1 = x0 + 1;
-  let y1 = y0 + 1;
-  let x_ratio = x - x0 as f32;
-  let y_ratio = y - y0 as f32;
+  let (x0, x1, x_ratio) = bilinear_axis_coords(x, width, tiling_mode);
+  let (y0, y1, y_ratio) = bilinear_axis_coords(y, height, tiling_mode);
 
   let texptr = texture.as_ptr();
   let x0y0 = unsafe {
@@ -148,6 +176,7 @@ fn magnitude(v: [f32; 3]) -> f32 {
   (v[0] + v[1] + v[2]) / 3.0
 }
 
+#[cfg(target_arch = "wasm32")]
 #[no_mangle]
 pub extern "C" fn malloc(size: usize) -> *mut u8 {
   let mut v = Vec::with_capacity(size);
@@ -156,6 +185,7 @@ pub extern "C" fn malloc(size: usize) -> *mut u8 {
   ptr
 }
 
+#[cfg(target_arch = "wasm32")]
 #[no_mangle]
 pub extern "C" fn free(ptr: *mut u8) {
   unsafe {
@@ -163,101 +193,308 @@ pub extern "C" fn free(ptr: *mut u8) {
   }
 }
 
+/// Computes the gradient of the texture's magnitude at the provided pixel using
+/// central differences of 4 neighboring samples, returning `(dx, dy)`.
+fn four_point_gradient(
+  texture_f32: &[f32],
+  width: usize,
+  height: usize,
+  x: usize,
+  y: usize,
+  tiling_mode: TilingMode,
+) -> (f32, f32) {
+  let step_x = 1.0 / width as f32;
+  let step_y = 1.0 / height as f32;
+
+  let d0 = [
+    texture_f32[(y * width + x) * 4],
+    texture_f32[(y * width + x) * 4 + 1],
+    texture_f32[(y * width + x) * 4 + 2],
+  ];
+  #[cfg(all(feature = "simd", target_arch = "wasm32"))]
+  let (d1, d2, d3, d4) = {
+    let d1 = read_interpolated_bilinear_f32_simd(
+      texture_f32,
+      width,
+      height,
+      x as f32 + step_x,
+      y as f32,
+      tiling_mode,
+    );
+    let d2 = read_interpolated_bilinear_f32_simd(
+      texture_f32,
+      width,
+      height,
+      x as f32 - step_x,
+      y as f32,
+      tiling_mode,
+    );
+    let d3 = read_interpolated_bilinear_f32_simd(
+      texture_f32,
+      width,
+      height,
+      x as f32,
+      y as f32 + step_y,
+      tiling_mode,
+    );
+    let d4 = read_interpolated_bilinear_f32_simd(
+      texture_f32,
+      width,
+      height,
+      x as f32,
+      y as f32 - step_y,
+      tiling_mode,
+    );
+    (d1, d2, d3, d4)
+  };
+
+  #[cfg(not(all(feature = "simd", target_arch = "wasm32")))]
+  let (d1, d2, d3, d4) = {
+    let d1 = read_interpolated_bilinear_f32(
+      texture_f32,
+      width,
+      height,
+      x as f32 + step_x,
+      y as f32,
+      tiling_mode,
+    );
+    let d2 = read_interpolated_bilinear_f32(
+      texture_f32,
+      width,
+      height,
+      x as f32 - step_x,
+      y as f32,
+      tiling_mode,
+    );
+    let d3 = read_interpolated_bilinear_f32(
+      texture_f32,
+      width,
+      height,
+      x as f32,
+      y as f32 + step_y,
+      tiling_mode,
+    );
+    let d4 = read_interpolated_bilinear_f32(
+      texture_f32,
+      width,
+      height,
+      x as f32,
+      y as f32 - step_y,
+      tiling_mode,
+    );
+    (d1, d2, d3, d4)
+  };
+
+  let dx = ((magnitude(d2) - magnitude(d0)) + (magnitude(d0) - magnitude(d1))) * 0.5;
+  let dy = ((magnitude(d4) - magnitude(d0)) + (magnitude(d0) - magnitude(d3))) * 0.5;
+  (dx, dy)
+}
+
+const SOBEL_X: [[f32; 3]; 3] = [[-1., 0., 1.], [-2., 0., 2.], [-1., 0., 1.]];
+const SOBEL_Y: [[f32; 3]; 3] = [[-1., -2., -1.], [0., 0., 0.], [1., 2., 1.]];
+/// The Sobel kernels produce 8x the per-pixel slope, but the 4-point stencil
+/// only measures about half of it (`s / 2 * (1 + 2 / width)` on a ramp of slope
+/// `s`) since it samples at sub-pixel offsets.  Scaling by 1/16 makes both
+/// kernels produce bumps of the same strength for the same `strength` param.
+const SOBEL_SCALE: f32 = 1. / 16.;
+
+/// Returns the index of the pixel `offset` pixels away from `v` along a single
+/// axis, handling pixels past the edge of the texture according to
+/// `tiling_mode`.
+fn offset_pixel_coord(v: usize, offset: isize, size: usize, tiling_mode: TilingMode) -> usize {
+  let v = v as isize + offset;
+  match tiling_mode {
+    TilingMode::Clamp => v.clamp(0, size as isize - 1) as usize,
+    TilingMode::Wrap => v.rem_euclid(size as isize) as usize,
+  }
+}
+
+/// Computes the gradient of the texture's magnitude at the provided pixel by
+/// applying the 3x3 Sobel operator, returning `(dx, dy)` with the same sign
+/// convention as `four_point_gradient` and scaled by `SOBEL_SCALE` to match it.
+#[cfg(not(all(feature = "simd", target_arch = "wasm32")))]
+fn sobel_gradient(
+  texture_f32: &[f32],
+  width: usize,
+  height: usize,
+  x: usize,
+  y: usize,
+  tiling_mode: TilingMode,
+) -> (f32, f32) {
+  let mut gx = 0.;
+  let mut gy = 0.;
+  for (row_ix, y_offset) in (-1..=1).enumerate() {
+    let sample_y = offset_pixel_coord(y, y_offset, height, tiling_mode);
+    for (col_ix, x_offset) in (-1..=1).enumerate() {
+      if row_ix == 1 && col_ix == 1 {
+        continue;
+      }
+
+      let sample_x = offset_pixel_coord(x, x_offset, width, tiling_mode);
+      let ix = (sample_y * width + sample_x) * 4;
+      let m = magnitude([texture_f32[ix], texture_f32[ix + 1], texture_f32[ix + 2]]);
+      gx += SOBEL_X[row_ix][col_ix] * m;
+      gy += SOBEL_Y[row_ix][col_ix] * m;
+    }
+  }
+
+  // The Sobel kernels point towards increasing values while the 4-point stencil
+  // points towards decreasing ones
+  (-gx * SOBEL_SCALE, -gy * SOBEL_SCALE)
+}
+
+/// SIMD version of `sobel_gradient`.  Since `magnitude` is linear, the kernels
+/// are applied to all channels at once and the magnitude is taken at the end.
+#[cfg(all(feature = "simd", target_arch = "wasm32"))]
+fn sobel_gradient(
+  texture_f32: &[f32],
+  width: usize,
+  height: usize,
+  x: usize,
+  y: usize,
+  tiling_mode: TilingMode,
+) -> (f32, f32) {
+  let texptr = texture_f32.as_ptr();
+  let mut gx = f32x4_splat(0.);
+  let mut gy = f32x4_splat(0.);
+  for (row_ix, y_offset) in (-1..=1).enumerate() {
+    let sample_y = offset_pixel_coord(y, y_offset, height, tiling_mode);
+    for (col_ix, x_offset) in (-1..=1).enumerate() {
+      if row_ix == 1 && col_ix == 1 {
+        continue;
+      }
+
+      let sample_x = offset_pixel_coord(x, x_offset, width, tiling_mode);
+      let px = unsafe { v128_load(texptr.add((sample_y * width + sample_x) * 4) as *const _) };
+      gx = f32x4_add(gx, f32x4_mul(f32x4_splat(SOBEL_X[row_ix][col_ix]), px));
+      gy = f32x4_add(gy, f32x4_mul(f32x4_splat(SOBEL_Y[row_ix][col_ix]), px));
+    }
+  }
+
+  let gx = magnitude([
+    f32x4_extract_lane::<0>(gx),
+    f32x4_extract_lane::<1>(gx),
+    f32x4_extract_lane::<2>(gx),
+  ]);
+  let gy = magnitude([
+    f32x4_extract_lane::<0>(gy),
+    f32x4_extract_lane::<1>(gy),
+    f32x4_extract_lane::<2>(gy),
+  ]);
+  (-gx * SOBEL_SCALE, -gy * SOBEL_SCALE)
+}
+
+enum PackMode {
+  /// No packing is done; the returned texture contains all 3 components of
+  /// the normal vector with 1 set for the alpha channel.
+  None,
+  /// The texture data is assumed to be in grayscale.  The returned RGBA
+  /// texture will have the normal vector packed into the GBA channels with
+  /// the provided texture data into the R channel.
+  GrayScaleGBA,
+}
+
+impl PackMode {
+  fn from_u8(pack_mode: u8) -> Self {
+    match pack_mode {
+      0 => PackMode::None,
+      1 => PackMode::GrayScaleGBA,
+      _ => panic!("Invalid pack mode"),
+    }
+  }
+
+  /// Returns the index of the first of the 3 channels that hold the normal
+  /// vector within each pixel.
+  fn normal_offset(&self) -> usize {
+    match self {
+      PackMode::None => 0,
+      PackMode::GrayScaleGBA => 1,
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+enum KernelType {
+  /// Central differences between 4 neighboring samples
+  FourPoint,
+  /// 3x3 Sobel operator.  Reads all 8 neighboring pixels and produces smoother,
+  /// less noisy normals.
+  Sobel,
+}
+
+impl KernelType {
+  fn from_u8(kernel_type: u8) -> Self {
+    match kernel_type {
+      0 => KernelType::FourPoint,
+      1 => KernelType::Sobel,
+      _ => panic!("Invalid kernel type"),
+    }
+  }
+}
+
+const DEFAULT_STRENGTH: f32 = 1.0;
+const DEFAULT_BIAS: f32 = 0.1;
+
+struct NormalMapParams {
+  pack_mode: PackMode,
+  /// Controls how neighboring pixels past the edges of the texture are sampled
+  tiling_mode: TilingMode,
+  /// Selects the stencil used to compute gradients
+  kernel_type: KernelType,
+  /// Multiplier on the computed gradients before normalization.  Higher values
+  /// produce more pronounced bumps and 0 produces a flat normal map.
+  strength: f32,
+  /// Controls the Z component of the normal before normalization
+  bias: f32,
+}
+
+impl Default for NormalMapParams {
+  fn default() -> Self {
+    NormalMapParams {
+      pack_mode: PackMode::None,
+      tiling_mode: TilingMode::Clamp,
+      kernel_type: KernelType::FourPoint,
+      strength: DEFAULT_STRENGTH,
+      bias: DEFAULT_BIAS,
+    }
+  }
+}
+
 /// Expect texture in RGBA format.  Returns normal map in RGBA format.
 ///
 /// Adapted from code by Jan Frischmuth <http://www.smart-page.net/blog>
-#[no_mangle]
-pub extern "C" fn gen_normal_map_from_texture(
-  texture: *const u8,
+fn gen_normal_map(
+  texture: &[u8],
   height: usize,
   width: usize,
-  pack_mode: u8,
-) -> *mut u8 {
-  let texture = unsafe { std::slice::from_raw_parts(texture, height * width * 4) };
+  params: &NormalMapParams,
+) -> Vec<u8> {
+  let NormalMapParams {
+    ref pack_mode,
+    tiling_mode,
+    kernel_type,
+    strength,
+    bias,
+  } = *params;
+
   let texture_f32 = texture
     .iter()
     .map(|&x| x as f32 / 255.0)
     .collect::<Vec<_>>();
 
-  enum PackMode {
-    /// No packing is done; the returned texture contains all 3 components of
-    /// the normal vector with 1 set for the alpha channel.
-    None,
-    /// The texture data is assumed to be in grayscale.  The returned RGBA
-    /// texture will have the normal vector packed into the GBA channels with
-    /// the provided texture data into the R channel.
-    GrayScaleGBA,
-  }
-
-  let pack_mode = match pack_mode {
-    0 => PackMode::None,
-    1 => PackMode::GrayScaleGBA,
-    _ => panic!("Invalid pack mode"),
-  };
-
   let pixel_count = texture.len() / 4;
   let mut normal_map = Vec::with_capacity(pixel_count * 4);
 
-  let step_x = 1.0 / width as f32;
-  let step_y = 1.0 / height as f32;
-
   for y in 0..height {
     for x in 0..width {
-      let d0 = [
-        texture_f32[(y * width + x) * 4],
-        texture_f32[(y * width + x) * 4 + 1],
-        texture_f32[(y * width + x) * 4 + 2],
-      ];
-      #[cfg(all(feature = "simd", target_arch = "wasm32"))]
-      let (d1, d2, d3, d4) = {
-        let d1 = read_interpolated_bilinear_f32_simd(
-          &texture_f32,
-          width,
-          height,
-          x as f32 + step_x,
-          y as f32,
-        );
-        let d2 = read_interpolated_bilinear_f32_simd(
-          &texture_f32,
-          width,
-          height,
-          x as f32 - step_x,
-          y as f32,
-        );
-        let d3 = read_interpolated_bilinear_f32_simd(
-          &texture_f32,
-          width,
-          height,
-          x as f32,
-          y as f32 + step_y,
-        );
-        let d4 = read_interpolated_bilinear_f32_simd(
-          &texture_f32,
-          width,
-          height,
-          x as f32,
-          y as f32 - step_y,
-        );
-        (d1, d2, d3, d4)
-      };
-
-      #[cfg(not(all(feature = "simd", target_arch = "wasm32")))]
-      let (d1, d2, d3, d4) = {
-        let d1 =
-          read_interpolated_bilinear_f32(&texture_f32, width, height, x as f32 + step_x, y as f32);
-        let d2 =
-          read_interpolated_bilinear_f32(&texture_f32, width, height, x as f32 - step_x, y as f32);
-        let d3 =
-          read_interpolated_bilinear_f32(&texture_f32, width, height, x as f32, y as f32 + step_y);
-        let d4 =
-          read_interpolated_bilinear_f32(&texture_f32, width, height, x as f32, y as f32 - step_y);
-        (d1, d2, d3, d4)
+      let (dx, dy) = match kernel_type {
+        KernelType::FourPoint => {
+          four_point_gradient(&texture_f32, width, height, x, y, tiling_mode)
+        }
+        KernelType::Sobel => sobel_gradient(&texture_f32, width, height, x, y, tiling_mode),
       };
+      let (dx, dy) = (dx * strength, dy * strength);
 
-      let dx = ((magnitude(d2) - magnitude(d0)) + (magnitude(d0) - magnitude(d1))) * 0.5;
-      let dy = ((magnitude(d4) - magnitude(d0)) + (magnitude(d0) - magnitude(d3))) * 0.5;
-
-      let bias = 0.1;
       let normal = normalize(dx, dy, 1.0 - ((bias - 0.1) / 100.0));
       let normal = [
         normal[0] * 0.5 + 0.5,
@@ -282,7 +519,365 @@ pub extern "C" fn gen_normal_map_from_texture(
     }
   }
 
+  normal_map
+}
+
+/// Expect texture in RGBA format.  Returns normal map in RGBA format.
+///
+/// Uses the default parameters; see `gen_normal_map_from_texture_v2` to
+/// configure them.
+#[no_mangle]
+pub extern "C" fn gen_normal_map_from_texture(
+  texture: *const u8,
+  height: usize,
+  width: usize,
+  pack_mode: u8,
+) -> *mut u8 {
+  gen_normal_map_from_texture_v2(
+    texture,
+    height,
+    width,
+    pack_mode,
+    0,
+    0,
+    DEFAULT_STRENGTH,
+    DEFAULT_BIAS,
+  )
+}
+
+/// Expect texture in RGBA format.  Returns normal map in RGBA format.
+///
+/// `tiling_mode` is 0 to clamp samples to the edges of the texture or 1 to wrap
+/// them around for seamlessly tiling textures.  `kernel_type` is 0 for the
+/// default 4-point stencil or 1 for a 3x3 Sobel operator.  `strength` is a
+/// multiplier on the computed gradients (default 1.0) and `bias` controls the Z
+/// component of the normal (default 0.1).
+#[no_mangle]
+pub extern "C" fn gen_normal_map_from_texture_v2(
+  texture: *const u8,
+  height: usize,
+  width: usize,
+  pack_mode: u8,
+  tiling_mode: u8,
+  kernel_type: u8,
+  strength: f32,
+  bias: f32,
+) -> *mut u8 {
+  let texture = unsafe { std::slice::from_raw_parts(texture, height * width * 4) };
+  let pack_mode = PackMode::from_u8(pack_mode);
+  let tiling_mode = TilingMode::from_u8(tiling_mode);
+  let kernel_type = KernelType::from_u8(kernel_type);
+
+  let params = NormalMapParams {
+    pack_mode,
+    tiling_mode,
+    kernel_type,
+    strength,
+    bias,
+  };
+
+  let mut normal_map = gen_normal_map(texture, height, width, &params);
   let ptr = normal_map.as_mut_ptr();
   std::mem::forget(normal_map);
   ptr
 }
+
+/// Returns the dimensions of the given level of a mip chain, halving each
+/// dimension per level down to a minimum of 1.
+fn mip_level_dims(width: usize, height: usize, level: usize) -> (usize, usize) {
+  ((width >> level).max(1), (height >> level).max(1))
+}
+
+/// Downsamples a normal map to half its size by averaging each 2x2 block of
+/// normals and re-normalizing the result.  Any non-normal channels are averaged
+/// as well.
+fn downsample_normal_map(
+  normal_map: &[u8],
+  width: usize,
+  height: usize,
+  pack_mode: &PackMode,
+) -> Vec<u8> {
+  let (out_width, out_height) = mip_level_dims(width, height, 1);
+  let normal_offset = pack_mode.normal_offset();
+  let mut out = Vec::with_capacity(out_width * out_height * 4);
+
+  for y in 0..out_height {
+    for x in 0..out_width {
+      let mut sum = [0f32; 4];
+      for (src_x, src_y) in [
+        (x * 2, y * 2),
+        (x * 2 + 1, y * 2),
+        (x * 2, y * 2 + 1),
+        (x * 2 + 1, y * 2 + 1),
+      ] {
+        // Odd dimensions can leave the last block with fewer than 4 pixels
+        let src_ix = (src_y.min(height - 1) * width + src_x.min(width - 1)) * 4;
+        for (channel_ix, sum) in sum.iter_mut().enumerate() {
+          *sum += normal_map[src_ix + channel_ix] as f32 / 255.;
+        }
+      }
+      let avg = sum.map(|v| v / 4.);
+
+      let normal = normalize(
+        avg[normal_offset] * 2. - 1.,
+        avg[normal_offset + 1] * 2. - 1.,
+        avg[normal_offset + 2] * 2. - 1.,
+      );
+      let mut px = avg.map(|v| (v * 255.) as u8);
+      for (channel_ix, component) in normal.into_iter().enumerate() {
+        px[normal_offset + channel_ix] = ((component * 0.5 + 0.5) * 255.) as u8;
+      }
+      out.extend_from_slice(&px);
+    }
+  }
+
+  out
+}
+
+/// Generates a normal map along with `mip_count - 1` successively downsampled
+/// levels below it.  All levels are packed sequentially into the returned
+/// buffer, starting with the full-resolution map.  `mip_count` must be at least
+/// 1.
+fn gen_normal_map_mip_chain(
+  texture: &[u8],
+  height: usize,
+  width: usize,
+  params: &NormalMapParams,
+  mip_count: usize,
+) -> Vec<u8> {
+  if mip_count == 0 {
+    panic!("Invalid mip count");
+  }
+
+  let mut out = gen_normal_map(texture, height, width, params);
+
+  let mut level_start = 0;
+  for level in 1..mip_count {
+    let (level_width, level_height) = mip_level_dims(width, height, level - 1);
+    let level_len = level_width * level_height * 4;
+    let downsampled = downsample_normal_map(
+      &out[level_start..level_start + level_len],
+      level_width,
+      level_height,
+      &params.pack_mode,
+    );
+    level_start += level_len;
+    out.extend_from_slice(&downsampled);
+  }
+
+  out
+}
+
+/// Expect texture in RGBA format.  Returns a normal map in RGBA format with
+/// `mip_count` mip levels packed sequentially, starting with the
+/// full-resolution map and halving each dimension at each successive level.
+/// `mip_count` must be at least 1.
+#[no_mangle]
+pub extern "C" fn gen_normal_map_mip(
+  texture: *const u8,
+  height: usize,
+  width: usize,
+  pack_mode: u8,
+  mip_count: usize,
+) -> *mut u8 {
+  let texture = unsafe { std::slice::from_raw_parts(texture, height * width * 4) };
+  let params = NormalMapParams {
+    pack_mode: PackMode::from_u8(pack_mode),
+    ..Default::default()
+  };
+
+  let mut normal_map = gen_normal_map_mip_chain(texture, height, width, &params, mip_count);
+  let ptr = normal_map.as_mut_ptr();
+  std::mem::forget(normal_map);
+  ptr
+}
+
+#[test]
+fn zero_strength_produces_flat_normal_map() {
+  let (width, height) = (8, 8);
+  let texture = (0..width * height)
+    .flat_map(|i| {
+      let v = ((i * 37) % 256) as u8;
+      [v, v, v, 255]
+    })
+    .collect::<Vec<_>>();
+
+  let normal_map = gen_normal_map(
+    &texture,
+    height,
+    width,
+    &NormalMapParams {
+      strength: 0.,
+      ..Default::default()
+    },
+  );
+  assert_eq!(normal_map.len(), texture.len());
+  for px in normal_map.chunks_exact(4) {
+    assert_eq!(px, [127, 127, 255, 255]);
+  }
+}
+
+#[test]
+fn wrap_mode_edges_match_periodic_interior() {
+  // Two periods of a profile whose first and last columns differ
+  let profile = [0u8, 30, 90, 100, 160, 150, 60, 20];
+  let (width, height) = (profile.len() * 2, 6);
+  let texture = (0..width * height)
+    .flat_map(|i| {
+      let v = profile[i % profile.len()];
+      [v, v, v, 255]
+    })
+    .collect::<Vec<_>>();
+
+  let gen = |tiling_mode| {
+    gen_normal_map(
+      &texture,
+      height,
+      width,
+      &NormalMapParams {
+        tiling_mode,
+        ..Default::default()
+      },
+    )
+  };
+  let px = |normal_map: &[u8], x: usize, y: usize| {
+    normal_map[(y * width + x) * 4..(y * width + x) * 4 + 4].to_owned()
+  };
+
+  // Edge columns have the same neighborhoods as the matching columns of the
+  // other period when wrapping
+  let wrapped = gen(TilingMode::Wrap);
+  for y in 0..height {
+    assert_eq!(px(&wrapped, 0, y), px(&wrapped, profile.len(), y));
+    assert_eq!(
+      px(&wrapped, width - 1, y),
+      px(&wrapped, profile.len() - 1, y)
+    );
+  }
+
+  let clamped = gen(TilingMode::Clamp);
+  assert_ne!(px(&clamped, 0, 0), px(&clamped, profile.len(), 0));
+}
+
+#[test]
+fn wrap_mode_samples_opposite_edge() {
+  assert_eq!(
+    bilinear_axis_coords(-0.25, 8, TilingMode::Wrap),
+    (7, 0, 0.75)
+  );
+  assert_eq!(bilinear_axis_coords(7.5, 8, TilingMode::Wrap), (7, 0, 0.5));
+  assert_eq!(
+    bilinear_axis_coords(-0.25, 8, TilingMode::Clamp),
+    (0, 1, 0.)
+  );
+  assert_eq!(bilinear_axis_coords(7.5, 8, TilingMode::Clamp), (6, 7, 0.));
+}
+
+#[cfg(test)]
+fn rms_tilt(normal_map: &[u8]) -> f32 {
+  let sum_sq = normal_map
+    .chunks_exact(4)
+    .map(|px| {
+      let nx = px[0] as f32 / 255. * 2. - 1.;
+      let ny = px[1] as f32 / 255. * 2. - 1.;
+      nx * nx + ny * ny
+    })
+    .sum::<f32>();
+  (sum_sq / (normal_map.len() / 4) as f32).sqrt()
+}
+
+#[test]
+fn sobel_kernel_on_gradient_texture() {
+  let (width, height) = (16, 16);
+  let texture = (0..width * height)
+    .flat_map(|i| {
+      let v = ((i % width) * 12) as u8;
+      [v, v, v, 255]
+    })
+    .collect::<Vec<_>>();
+
+  let gen = |kernel_type| {
+    gen_normal_map(
+      &texture,
+      height,
+      width,
+      &NormalMapParams {
+        kernel_type,
+        ..Default::default()
+      },
+    )
+  };
+  let four_point = gen(KernelType::FourPoint);
+  let sobel = gen(KernelType::Sobel);
+
+  // Both kernels tilt the normals; see `sobel_kernel_scale_matches_four_point`
+  // for their relative strength
+  assert!(rms_tilt(&four_point) > 0.);
+  assert!(rms_tilt(&sobel) > 0.);
+
+  // Sobel produces a uniform tilt away from the edges
+  let interior_px = |x: usize, y: usize| &sobel[(y * width + x) * 4..(y * width + x) * 4 + 4];
+  for y in 0..height {
+    for x in 1..width - 1 {
+      assert_eq!(interior_px(x, y), interior_px(1, 0));
+    }
+  }
+}
+
+#[test]
+fn sobel_kernel_scale_matches_four_point() {
+  let (width, height) = (256, 4);
+  let slope = 0.002;
+  let texture_f32 = (0..width * height)
+    .flat_map(|i| {
+      let v = (i % width) as f32 * slope;
+      [v, v, v, 1.]
+    })
+    .collect::<Vec<_>>();
+
+  let (x, y) = (width / 2, height / 2);
+  let (four_point_dx, _) =
+    four_point_gradient(&texture_f32, width, height, x, y, TilingMode::Clamp);
+  let (sobel_dx, sobel_dy) = sobel_gradient(&texture_f32, width, height, x, y, TilingMode::Clamp);
+  assert!(four_point_dx < 0.);
+  assert!((sobel_dx / four_point_dx - 1.).abs() < 0.02);
+  assert_eq!(sobel_dy, 0.);
+}
+
+#[test]
+fn mip_chain_contains_all_levels() {
+  let (width, height) = (16, 16);
+  let texture = (0..width * height)
+    .flat_map(|i| {
+      let v = ((i * 53) % 256) as u8;
+      [v, v, v, 255]
+    })
+    .collect::<Vec<_>>();
+
+  let mip_count = 5;
+  let mip_chain = gen_normal_map_mip_chain(
+    &texture,
+    height,
+    width,
+    &NormalMapParams::default(),
+    mip_count,
+  );
+  // 16x16 + 8x8 + 4x4 + 2x2 + 1x1
+  assert_eq!(mip_chain.len() / 4, 256 + 64 + 16 + 4 + 1);
+  assert!((mip_chain.len() / 4) as f32 <= (width * height) as f32 * 4. / 3.);
+
+  // Every level should still contain unit-length normals
+  for px in mip_chain.chunks_exact(4) {
+    let normal = [0, 1, 2].map(|i| px[i] as f32 / 255. * 2. - 1.);
+    let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+    assert!((len - 1.).abs() < 0.02, "{len}");
+    assert_eq!(px[3], 255);
+  }
+}
+
+#[test]
+#[should_panic(expected = "Invalid mip count")]
+fn mip_chain_rejects_zero_mip_count() {
+  let texture = [90u8, 90, 90, 255].repeat(4 * 4);
+  gen_normal_map_mip_chain(&texture, 4, 4, &NormalMapParams::default(), 0);
+}
@@ -128,11 +128,56 @@ fn get_texture_indices_for_corner(
   }
 }
 
+/// Controls how the position within the crossfade region is mapped to the
+/// weights used to blend between textures.
+#[derive(Clone, Copy)]
+enum BlendMode {
+  Linear,
+  /// `t * t * (3 - 2 * t)`
+  Smoothstep,
+  /// `6t^5 - 15t^4 + 10t^3`, which also has zero second derivative at the edges
+  /// of the crossfade region
+  Quintic,
+}
+
+impl BlendMode {
+  fn from_u8(blend_mode: u8) -> Self {
+    match blend_mode {
+      0 => BlendMode::Linear,
+      1 => BlendMode::Smoothstep,
+      2 => BlendMode::Quintic,
+      _ => panic!("Invalid blend mode"),
+    }
+  }
+
+  fn apply(self, t: f32) -> f32 {
+    match self {
+      BlendMode::Linear => t,
+      BlendMode::Smoothstep => t * t * (3. - 2. * t),
+      BlendMode::Quintic => t * t * t * (t * (t * 6. - 15.) + 10.),
+    }
+  }
+}
+
+#[test]
+fn blend_mode_curves() {
+  for mode in [BlendMode::Linear, BlendMode::Smoothstep, BlendMode::Quintic] {
+    assert_eq!(mode.apply(0.), 0.);
+    assert_eq!(mode.apply(0.5), 0.5);
+    assert_eq!(mode.apply(1.), 1.);
+  }
+  assert_eq!(BlendMode::Smoothstep.apply(0.25), 0.15625);
+  assert!((BlendMode::Quintic.apply(0.25) - 0.103515625).abs() < 1e-6);
+}
+
+/// `blend_mode` is 0 for linear, 1 for smoothstep, or 2 for quintic blending
+/// between textures in the crossfade region.
 #[no_mangle]
-pub extern "C" fn generate(size: usize, threshold: f32) -> *mut u8 {
+pub extern "C" fn generate(size: usize, threshold: f32, blend_mode: u8) -> *mut u8 {
   if threshold < 0. || threshold > 1. {
     panic!("Threshold must be between 0 and 1");
   }
+  let blend_mode = BlendMode::from_u8(blend_mode);
 
   let mut textures = unsafe { &TEXTURE_PTRS }
     .iter()
@@ -140,9 +185,8 @@ pub extern "C" fn generate(size: usize, threshold: f32) -> *mut u8 {
     .map(|&data| unsafe { std::slice::from_raw_parts_mut(data, size * size * 4) })
     .collect::<Vec<_>>();
 
-  // textures count must be a power of 2
-  if textures.len().count_ones() != 1 {
-    panic!("Textures count must be a power of 2");
+  if textures.is_empty() {
+    panic!("At least one texture must be set");
   }
 
   // DEBUG
@@ -189,6 +233,19 @@ pub extern "C" fn generate(size: usize, threshold: f32) -> *mut u8 {
   // }
   // END DEBUG
 
+  let out = crossfade(&textures, size, threshold, blend_mode);
+  Box::into_raw(out.into_boxed_slice()) as *mut u8
+}
+
+/// Generates a `textures.len()` x `textures.len()` grid of tiles where each
+/// tile is crossfaded into its neighbors within `threshold` of its edges.  Any
+/// number of textures is supported.
+fn crossfade<T: AsRef<[u8]>>(
+  textures: &[T],
+  size: usize,
+  threshold: f32,
+  blend_mode: BlendMode,
+) -> Vec<u8> {
   let out_size = size * textures.len();
   let mut out: Vec<u8> = Vec::with_capacity(out_size * out_size * 4);
   for y in 0..out_size {
@@ -209,30 +266,30 @@ pub extern "C" fn generate(size: usize, threshold: f32) -> *mut u8 {
         match project_box_coord(x_cur_tile_progress, y_cur_tile_progress, threshold) {
           o => o,
         };
-      let normalized_x = (normalized_x + 1.) / 2.;
-      let normalized_y = (normalized_y + 1.) / 2.;
+      let normalized_x = blend_mode.apply((normalized_x + 1.) / 2.);
+      let normalized_y = blend_mode.apply((normalized_y + 1.) / 2.);
 
       let (top_left_ix, top_right_ix, bot_left_ix, bot_right_ix) =
         get_texture_indices_for_corner(textures.len(), base_tx_ix, x_side, y_side);
-      let top_left_texture = &*textures[top_left_ix];
+      let top_left_texture = textures[top_left_ix].as_ref();
       let top_left_sample = [
         top_left_texture[base_texture_ix + 0] as f32, // * tl_weight,
         top_left_texture[base_texture_ix + 1] as f32, // * tl_weight,
         top_left_texture[base_texture_ix + 2] as f32, // * tl_weight,
       ];
-      let top_right_texture = &*textures[top_right_ix];
+      let top_right_texture = textures[top_right_ix].as_ref();
       let top_right_sample = [
         top_right_texture[base_texture_ix + 0] as f32, // * tr_weight,
         top_right_texture[base_texture_ix + 1] as f32, // * tr_weight,
         top_right_texture[base_texture_ix + 2] as f32, // * tr_weight,
       ];
-      let bot_left_texture = &*textures[bot_left_ix];
+      let bot_left_texture = textures[bot_left_ix].as_ref();
       let bot_left_sample = [
         bot_left_texture[base_texture_ix + 0] as f32, // * bl_weight,
         bot_left_texture[base_texture_ix + 1] as f32, // * bl_weight,
         bot_left_texture[base_texture_ix + 2] as f32, // * bl_weight,
       ];
-      let bot_right_texture = &*textures[bot_right_ix];
+      let bot_right_texture = textures[bot_right_ix].as_ref();
       let bot_right_sample = [
         bot_right_texture[base_texture_ix + 0] as f32, // * br_weight,
         bot_right_texture[base_texture_ix + 1] as f32, // * br_weight,
@@ -263,5 +320,63 @@ pub extern "C" fn generate(size: usize, threshold: f32) -> *mut u8 {
     }
   }
 
-  Box::into_raw(out.into_boxed_slice()) as *mut u8
+  out
+}
+
+#[test]
+fn crossfade_non_power_of_2_texture_counts() {
+  let size = 16;
+  for texture_count in [3, 6] {
+    let textures = (0..texture_count)
+      .map(|i| {
+        let v = (i * 40) as u8;
+        [v, 255 - v, v / 2, 255].repeat(size * size)
+      })
+      .collect::<Vec<_>>();
+    let max = ((texture_count - 1) * 40) as u8;
+
+    let out = crossfade(&textures, size, 0.2, BlendMode::Linear);
+    assert_eq!(out.len(), (size * texture_count).pow(2) * 4);
+    for px in out.chunks_exact(4) {
+      assert!(px[0] <= max);
+      assert!(px[1] >= 255 - max);
+      assert!(px[2] <= max / 2);
+      assert_eq!(px[3], 255);
+    }
+  }
+}
+
+#[test]
+fn smoothstep_blend_differs_from_linear_in_transition_zone() {
+  let size = 20;
+  let textures = [
+    [0u8, 0, 0, 255].repeat(size * size),
+    [200u8, 200, 200, 255].repeat(size * size),
+  ];
+  let threshold = 0.5;
+
+  let linear = crossfade(&textures, size, threshold, BlendMode::Linear);
+  let smoothstep = crossfade(&textures, size, threshold, BlendMode::Smoothstep);
+
+  // Walk along the middle row of the first tile, which crosses the transition
+  // zone on either side and the unblended region in the middle
+  let y = size / 2;
+  let row = |out: &[u8]| {
+    (0..size)
+      .map(|x| out[(y * size * textures.len() + x) * 4])
+      .collect::<Vec<_>>()
+  };
+  let linear_row = row(&linear);
+  let smoothstep_row = row(&smoothstep);
+  assert_ne!(linear_row, smoothstep_row);
+
+  let in_transition_zone = |x: usize| {
+    let progress = x as f32 / size as f32;
+    progress < threshold / 2. || progress > 1. - threshold / 2.
+  };
+  for x in 0..size {
+    if !in_transition_zone(x) {
+      assert_eq!(linear_row[x], smoothstep_row[x]);
+    }
+  }
 }